@@ -0,0 +1,166 @@
+//! RISC-V Debug Transport Module (DTM) access over JTAG.
+//!
+//! This sits alongside the ARM ADIv5 DPACC/APACC helpers in [`crate::jtag`], driving the
+//! RISC-V Debug Module Interface (DMI) described by the RISC-V Debug Specification so this
+//! crate can also act as a DMI-over-JTAG probe (e.g. for the microwatt/OpenPOWER bring-up
+//! flow). It builds on the same low-level scan primitives `jtag` uses internally, rather than
+//! introducing a second way to drive the wire.
+
+use crate::jtag::{
+    bypass_after_data, shift_repeated_tdi, shift_tdi, Jtag, TransferResult, EXIT1_TO_IDLE,
+    IDLE_TO_SHIFT_DR,
+};
+
+/// `DTMCS`: the RISC-V Debug Transport Module Control and Status register.
+pub const JTAG_IR_DTMCS: u32 = 0x10;
+/// `DMI`: the RISC-V Debug Module Interface register.
+pub const JTAG_IR_DMI: u32 = 0x11;
+
+/// The RISC-V debug spec's `dmistat`/op success code.
+const DMI_OP_SUCCESS: u32 = 0;
+/// The RISC-V debug spec's op code for a failed operation.
+const DMI_OP_FAILED: u32 = 2;
+// Op code 3 ("busy") and any other value are both handled by the retry arm below.
+
+/// The operation requested by a [`dmi_transfer`] call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DmiOp {
+    Nop = 0,
+    Read = 1,
+    Write = 2,
+}
+
+/// Decoded contents of the `DTMCS` register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Dtmcs {
+    /// Version of the debug transport module.
+    pub version: u8,
+    /// Number of address bits in the DMI address field.
+    pub abits: u8,
+    /// Minimum number of Run-Test/Idle cycles the DTM requires between scans.
+    pub idle: u8,
+    /// Status of the last DMI operation, using the same encoding as the op field captured by a
+    /// DMI scan (0 = success, 2 = failed, 3 = busy).
+    pub dmistat: u8,
+}
+
+/// Reads and decodes the `DTMCS` register.
+pub fn read_dtmcs<DEPS>(jtag: &mut impl Jtag<DEPS>) -> Dtmcs {
+    jtag.shift_ir(JTAG_IR_DTMCS);
+    let dtmcs = jtag.shift_dr(0);
+
+    Dtmcs {
+        version: (dtmcs & 0xF) as u8,
+        abits: ((dtmcs >> 4) & 0x3F) as u8,
+        dmistat: ((dtmcs >> 10) & 0x3) as u8,
+        idle: ((dtmcs >> 12) & 0x7) as u8,
+    }
+}
+
+/// Issues a `dmireset` through `DTMCS`, clearing a sticky busy/error condition.
+fn dmi_reset<DEPS>(jtag: &mut impl Jtag<DEPS>) {
+    const DTMCS_DMIRESET: u32 = 1 << 16;
+
+    jtag.shift_ir(JTAG_IR_DTMCS);
+    jtag.shift_dr(DTMCS_DMIRESET);
+}
+
+/// Executes a single DMI transfer, retrying on a busy response.
+///
+/// Shifts a `DMI` register of `abits + 34` bits: the low 2 bits select `op`, the next 32 bits
+/// carry `data`, and the top `abits` bits carry `address`. DMI is posted/pipelined just like the
+/// ADIv5 DPACC/APACC scans in `jtag.rs`: the op/data field captured by a scan reflects the
+/// *previous* scan's request, not the one just shifted in. So after submitting the request this
+/// issues a follow-up `nop` scan (the DMI equivalent of the extra flush scan `transfer_block`
+/// issues after ADIv5 posted reads) to actually retrieve its result. On a busy response this
+/// issues a `dmireset` and retries the whole request with at least `dtmcs.idle` Run-Test/Idle
+/// cycles inserted, mirroring the `idle_cycles` handling in [`crate::jtag::Jtag::transfer`], up
+/// to `retries` times.
+pub fn dmi_transfer<DEPS>(
+    jtag: &mut impl Jtag<DEPS>,
+    abits: u8,
+    address: u32,
+    data: u32,
+    op: DmiOp,
+    idle_cycles: u8,
+    retries: u8,
+) -> TransferResult {
+    jtag.shift_ir(JTAG_IR_DMI);
+
+    let dr_bits = abits + 34;
+    let request = ((address as u64) << 34) | ((data as u64) << 2) | op as u64;
+    let nop = DmiOp::Nop as u64;
+
+    let mut idle_cycles = idle_cycles;
+    for _ in 0..=retries {
+        shift_dmi_dr(jtag, request, dr_bits, idle_cycles);
+        let captured = shift_dmi_dr(jtag, nop, dr_bits, idle_cycles);
+        let result_op = (captured & 0x3) as u32;
+
+        match result_op {
+            DMI_OP_SUCCESS => return TransferResult::Ok(((captured >> 2) & 0xFFFF_FFFF) as u32),
+            DMI_OP_FAILED => return TransferResult::Fault,
+            // DMI_OP_BUSY, and anything else the spec doesn't define: reset and retry.
+            _ => {
+                dmi_reset(jtag);
+                idle_cycles = idle_cycles.max(read_dtmcs(jtag).idle);
+                // `dmi_reset`/`read_dtmcs` both shift through DTMCS, leaving IR pointed at it
+                // instead of DMI; point it back before the next iteration re-shifts the DMI DR.
+                jtag.shift_ir(JTAG_IR_DMI);
+            }
+        }
+    }
+
+    TransferResult::Wait
+}
+
+/// Shifts `bits` bits of `data` through the currently-selected DR (assumed to already be `DMI`),
+/// walking Run-Test/Idle -> Shift-DR -> Exit1 -> Idle exactly like the ADIv5 `transfer` helper
+/// in [`crate::jtag`], but over a register wider than 32 bits.
+fn shift_dmi_dr<DEPS>(jtag: &mut impl Jtag<DEPS>, data: u64, bits: u8, idle_cycles: u8) -> u64 {
+    jtag.tms_sequence(IDLE_TO_SHIFT_DR);
+
+    let device_index = jtag.config().index as usize;
+    let device_count = jtag.config().device_count as usize;
+    let bypass_bits_before = device_index as u16;
+    let bypass_bits_after = device_count as u16 - bypass_bits_before - 1;
+
+    shift_repeated_tdi(jtag, 0xFF, bypass_bits_before, false);
+    let captured = shift_register_data_u64(jtag, data, bits, bypass_bits_after == 0);
+    bypass_after_data(jtag, bypass_bits_after);
+
+    jtag.tms_sequence(EXIT1_TO_IDLE);
+    shift_repeated_tdi(jtag, 0xFF, idle_cycles as u16, false);
+
+    captured
+}
+
+/// Shift out data, assuming to already be in Shift-DR. Identical to
+/// `jtag::shift_register_data`, except over a `u64` for DMI registers wider than 32 bits.
+fn shift_register_data_u64<DEPS>(
+    jtag: &mut impl Jtag<DEPS>,
+    mut data: u64,
+    clock_cycles: u8,
+    exit_shift: bool,
+) -> u64 {
+    let mut captured = 0u64;
+    let mut clocks = clock_cycles;
+    while clocks > 1 {
+        let bits = (clocks - 1).min(8);
+
+        let captured_byte = shift_tdi(jtag, data as u8, bits, false);
+        captured >>= bits;
+        captured |= (captured_byte as u64) << (clock_cycles - bits);
+
+        data >>= bits;
+        clocks -= bits;
+    }
+
+    let captured_byte = shift_tdi(jtag, data as u8, 1, exit_shift);
+    captured >>= 1;
+    captured |= (captured_byte as u64) << (clock_cycles - 1);
+
+    captured
+}
@@ -168,10 +168,10 @@ impl Config {
     }
 }
 
-const IDLE_TO_SHIFT_DR: &[bool] = &[true, false, false];
+pub(crate) const IDLE_TO_SHIFT_DR: &[bool] = &[true, false, false];
 const IDLE_TO_SHIFT_IR: &[bool] = &[true, true, false, false];
 const SHIFT_TO_IDLE: &[bool] = &[true, true, false];
-const EXIT1_TO_IDLE: &[bool] = &[true, false];
+pub(crate) const EXIT1_TO_IDLE: &[bool] = &[true, false];
 
 pub(crate) const JTAG_IR_ABORT: u32 = 0x08;
 pub(crate) const JTAG_IR_DPACC: u32 = 0x0A;
@@ -276,15 +276,311 @@ pub trait Jtag<DEPS>: From<DEPS> {
     ///
     /// This function executes the data part of a DPACC or APACC scan, starting from Test/Idle
     /// and ending in Test/Idle, after shifting out idle bits.
+    ///
+    /// If `match_value` is set, `data` is instead the expected value: the register is read
+    /// repeatedly, ANDed with `transfer_config.match_mask`, until it matches `data` (also masked)
+    /// or the configured match-retry budget is exhausted, implementing CMSIS-DAP's register-match
+    /// semantics.
+    ///
+    /// INCOMPLETE: this only implements the JTAG-side retry/compare loop. It reads whatever
+    /// `transfer_config.match_mask` already contains, but nothing in this crate yet writes that
+    /// field from an incoming `DAP_TRANSFER_MATCH_MASK` request — the `dap` dispatcher still
+    /// needs a case that decodes that request and stores the mask into `TransferConfig` (shared
+    /// with the SWD side) before `DAP_TRANSFER_MATCH_VALUE` requests can work end-to-end. Until
+    /// that lands, `match_value` transfers silently compare against a stale/default mask.
     fn transfer(
         &mut self,
         r_nw: RnW,
         a2a3: u8,
         transfer_config: &TransferConfig,
         data: u32,
+        match_value: bool,
     ) -> TransferResult {
+        if match_value {
+            return match_transfer(self, a2a3, transfer_config, data);
+        }
         transfer(self, r_nw, a2a3, transfer_config.idle_cycles, data, true)
     }
+
+    /// Executes a block of consecutive transfers to the same register, moving straight from
+    /// Exit1-DR back into Shift-DR between words instead of walking all the way out to
+    /// Run-Test/Idle (and back in) per word like [`Jtag::transfer`] does.
+    ///
+    /// For writes, `data` holds the words to write and `out` is unused. For reads, `data` is
+    /// unused and `out` is filled with the words read; this implements the standard ADIv5
+    /// "posted read" behaviour also used by `Jtag::transfer` itself (the value captured by scan
+    /// *n* corresponds to the request issued by scan *n - 1*), so one extra scan is issued after
+    /// the last requested word to flush out its result, matching how CMSIS-DAP's
+    /// `DAP_TransferBlock` is expected to perform. Aborts on the first non-OK/FAULT ack, the
+    /// same as `transfer`.
+    ///
+    /// The per-word [`Jtag::transfer`] remains the fallback for transports where overriding this
+    /// default doesn't pay for itself.
+    fn transfer_block(
+        &mut self,
+        r_nw: RnW,
+        a2a3: u8,
+        transfer_config: &TransferConfig,
+        data: &[u32],
+        out: &mut [u32],
+    ) -> TransferResult {
+        let count = match r_nw {
+            RnW::W => data.len(),
+            RnW::R => out.len(),
+        };
+        if count == 0 {
+            return TransferResult::Ok(0);
+        }
+
+        self.tms_sequence(IDLE_TO_SHIFT_DR);
+
+        let device_index = self.config().index as usize;
+        let device_count = self.config().device_count as usize;
+        let bypass_bits_before = device_index as u16;
+        let bypass_bits_after = device_count as u16 - bypass_bits_before - 1;
+
+        let addr_dr = (a2a3 << 1) as u32 | (r_nw as u32);
+
+        // Reads take one extra scan to flush out the last posted result; writes take none.
+        let last_scan = match r_nw {
+            RnW::R => count,
+            RnW::W => count - 1,
+        };
+
+        for scan in 0..=last_scan {
+            // Every device on the chain sits in this same DR shift register, so the bypass
+            // padding has to be re-sent on every scan, not just the first/last.
+            shift_repeated_tdi(self, 0xFF, bypass_bits_before, false);
+
+            let ack = shift_register_data(self, addr_dr, 3, false);
+            if ack != DAP_TRANSFER_OK_FAULT {
+                self.tms_sequence(SHIFT_TO_IDLE);
+                shift_repeated_tdi(self, 0xFF, transfer_config.idle_cycles as u16, false);
+                return TransferResult::Wait;
+            }
+
+            let write_word = match r_nw {
+                RnW::W if scan < count => data[scan],
+                _ => 0,
+            };
+            let captured = shift_register_data(self, write_word, 32, bypass_bits_after == 0);
+            bypass_after_data(self, bypass_bits_after);
+
+            if matches!(r_nw, RnW::R) && scan > 0 {
+                out[scan - 1] = captured;
+            }
+
+            if scan == last_scan {
+                self.tms_sequence(EXIT1_TO_IDLE);
+            } else {
+                // Exit1-DR -> Update-DR -> Select-DR-Scan -> Capture-DR -> Shift-DR: this is
+                // what actually latches the word just shifted in (Update-DR) before starting
+                // the next scan, instead of overwriting it with the next word before it's ever
+                // applied.
+                self.tms_sequence(&[true, true, false, false]);
+            }
+        }
+
+        shift_repeated_tdi(self, 0xFF, transfer_config.idle_cycles as u16, false);
+
+        TransferResult::Ok(0)
+    }
+
+    /// Discovers the JTAG scan chain without requiring a pre-populated `scan_chain`.
+    ///
+    /// This only uses the low-level `tms_sequence`/`sequence` primitives, the same way tools
+    /// like ecpdap and probe-rs perform a "blind" scan-chain scan: every TAP is forced into
+    /// BYPASS to count devices, IDCODEs are recovered from the DR contents that follow a
+    /// Test-Logic-Reset, and the total IR length is measured to split it across the detected
+    /// TAPs. Unknown IDCODEs (or IR lengths that can't otherwise be attributed) fall back to
+    /// assigning the measured IR bits that the known TAPs don't account for to the single TAP
+    /// whose IR length is unknown.
+    ///
+    /// Returns `false` if no TAP responds, if the TAP count is not less than `scan_chain.len()`,
+    /// if the total IR length can't be measured, or if more than one TAP's IR length is unknown.
+    fn autodetect(&mut self) -> bool {
+        // Force Test-Logic-Reset, then move to Run-Test/Idle.
+        self.tms_sequence(&[true, true, true, true, true, false]);
+
+        // An all-ones IR is the mandatory BYPASS encoding in IEEE 1149.1, regardless of the
+        // (yet unknown) IR length, so this puts every TAP into BYPASS in one pass.
+        self.tms_sequence(IDLE_TO_SHIFT_IR);
+        shift_repeated_tdi(self, 0xFF, AUTODETECT_MAX_BITS, false);
+        self.tms_sequence(SHIFT_TO_IDLE);
+
+        // Every BYPASS register is exactly one bit, so flushing zeroes and then counting the
+        // clocks before the first one emerges from TDO gives the number of TAPs on the chain.
+        self.tms_sequence(IDLE_TO_SHIFT_DR);
+        shift_repeated_tdi(self, 0x00, AUTODETECT_MAX_BITS, false);
+        let device_count = clock_until_high(self, AUTODETECT_MAX_BITS);
+        self.tms_sequence(SHIFT_TO_IDLE);
+
+        // Hitting the probe bound means TDO never went high, i.e. no TAP ever responded with its
+        // BYPASS bit (broken/disconnected chain) -- not "0 TAPs found"; a count of 0 means TDO
+        // was already high with no BYPASS bit in the path. `device_count` must also fit `u8`
+        // before it's used as a `scan_chain`/loop index below.
+        if device_count == 0 || device_count >= AUTODETECT_MAX_BITS || device_count > u8::MAX as u16
+        {
+            warn!("JTAG scan-chain autodetection found no responding TAPs");
+            return false;
+        }
+        let device_count = device_count as u8;
+
+        if !self.config().update_device_count(device_count) {
+            return false;
+        }
+
+        // After a fresh Test-Logic-Reset, every TAP's DR holds either its IDCODE (if IDCODE is
+        // the default instruction) or a single 0 bit (if BYPASS is the default instruction), so
+        // one more reset followed by a single Shift-DR recovers both in one pass.
+        self.tms_sequence(&[true, true, true, true, true, false]);
+        self.tms_sequence(IDLE_TO_SHIFT_DR);
+        for read_index in 0..device_count {
+            let has_idcode = shift_tdi(self, 0x00, 1, false) & 1 != 0;
+            let tap = if has_idcode {
+                let mut idcode = 1u32;
+                for bit in 1..32 {
+                    idcode |= (shift_tdi(self, 0x00, 1, false) as u32 & 1) << bit;
+                }
+                TapConfig {
+                    ir_length: ir_length_for_idcode(idcode).unwrap_or(0),
+                    ir_before: 0,
+                    ir_after: 0,
+                }
+            } else {
+                TapConfig::INIT
+            };
+            // `scan_chain[0]` is the TAP nearest TDO (`shift_dr`/`transfer` shift `index` bypass
+            // bits ahead of the data), and TDO yields the TAP nearest TDO first, so the read
+            // order is already the `scan_chain` order.
+            self.config().scan_chain[read_index as usize] = tap;
+        }
+        self.tms_sequence(SHIFT_TO_IDLE);
+
+        // Measure the total IR length the same way the device count was measured above.
+        self.tms_sequence(IDLE_TO_SHIFT_IR);
+        shift_repeated_tdi(self, 0x00, AUTODETECT_MAX_BITS, false);
+        let total_ir_bits = clock_until_high(self, AUTODETECT_MAX_BITS);
+        self.tms_sequence(SHIFT_TO_IDLE);
+
+        // Same reasoning as the device-count check above: hitting the bound means TDO never
+        // went high, so the IR length could not actually be measured.
+        if total_ir_bits >= AUTODETECT_MAX_BITS {
+            warn!("JTAG scan-chain autodetection could not measure a total IR length");
+            return false;
+        }
+
+        if !distribute_ir_length(self.config(), device_count, total_ir_bits) {
+            warn!("Could not attribute measured IR bits to an unambiguous TAP layout");
+            return false;
+        }
+
+        info!(
+            "Autodetected {} JTAG TAP(s), {} total IR bits",
+            device_count, total_ir_bits
+        );
+        true
+    }
+}
+
+/// Maximum number of bits used when probing an unknown scan chain in [`Jtag::autodetect`].
+///
+/// This only needs to be larger than the total device count / IR length of the chains this
+/// crate is likely to encounter; it bounds the flush/count loops so they terminate even when
+/// nothing is connected.
+const AUTODETECT_MAX_BITS: u16 = 512;
+
+/// Known IDCODE -> IR length mappings, for parts whose IR width can't be derived from IDCODE
+/// alone (the rest fall back to the single-unknown-TAP heuristic in [`distribute_ir_length`]).
+const KNOWN_IDCODE_IR_LENGTHS: &[(u32, u8)] = &[
+    // Xilinx xc7-series FPGAs use a 6-bit IR.
+    (0x0362_D093, 6),
+];
+
+fn ir_length_for_idcode(idcode: u32) -> Option<u8> {
+    KNOWN_IDCODE_IR_LENGTHS
+        .iter()
+        .find(|(code, _)| *code == idcode)
+        .map(|(_, ir_length)| *ir_length)
+}
+
+/// Clocks 1-bits into TDI, without leaving the current shift state, until the first 1 is
+/// captured on TDO. Returns the number of clocks before the one that captured the 1, i.e. the
+/// length of the zero-filled shift path, used to count BYPASS bits (device count) and to
+/// measure total IR length. Returns `max_clocks` verbatim if TDO never went high, which callers
+/// must treat as "could not measure", not as a legitimate count of `max_clocks`.
+fn clock_until_high<DEPS>(jtag: &mut impl Jtag<DEPS>, max_clocks: u16) -> u16 {
+    for count in 0..max_clocks {
+        if shift_tdi(jtag, 0xFF, 1, false) & 1 != 0 {
+            return count;
+        }
+    }
+    max_clocks
+}
+
+/// Fills in `ir_length`/`ir_before`/`ir_after` for the first `device_count` entries of
+/// `config.scan_chain`, given the already-known per-device IR lengths (0 meaning unknown) and
+/// the total measured IR length.
+///
+/// Returns `false` if more than one TAP has an unrecognized IDCODE on a multi-TAP chain, since
+/// there is then no way to attribute the measured IR length to a specific TAP; callers must not
+/// trust `scan_chain` in that case.
+fn distribute_ir_length(config: &mut Config, device_count: u8, total_ir_bits: u16) -> bool {
+    let taps = &mut config.scan_chain[..device_count as usize];
+
+    let known_ir_bits: u16 = taps.iter().map(|tap| tap.ir_length as u16).sum();
+    let unknown_taps = taps.iter().filter(|tap| tap.ir_length == 0).count();
+
+    if unknown_taps == 1 {
+        let remaining = total_ir_bits.saturating_sub(known_ir_bits);
+        if remaining > u8::MAX as u16 {
+            return false;
+        }
+        for tap in taps.iter_mut() {
+            if tap.ir_length == 0 {
+                tap.ir_length = remaining as u8;
+            }
+        }
+    } else if unknown_taps > 1 {
+        if device_count != 1 || total_ir_bits > u8::MAX as u16 {
+            return false;
+        }
+        taps[0].ir_length = total_ir_bits as u8;
+    }
+
+    let mut offset: u16 = 0;
+    for tap in taps.iter_mut() {
+        tap.ir_before = offset;
+        offset += tap.ir_length as u16;
+    }
+    for tap in taps.iter_mut() {
+        tap.ir_after = total_ir_bits.saturating_sub(tap.ir_before + tap.ir_length as u16);
+    }
+
+    true
+}
+
+/// Implements CMSIS-DAP register-match semantics: re-reads the register at `a2a3` until the
+/// captured value, ANDed with `transfer_config.match_mask`, equals `expected` (also masked),
+/// retrying up to `transfer_config.match_retry` times with `idle_cycles` inserted between
+/// attempts. Returns `TransferResult::Mismatch` once the retry budget is exhausted.
+fn match_transfer<DEPS>(
+    jtag: &mut impl Jtag<DEPS>,
+    a2a3: u8,
+    transfer_config: &TransferConfig,
+    expected: u32,
+) -> TransferResult {
+    for _ in 0..=transfer_config.match_retry {
+        match transfer(jtag, RnW::R, a2a3, transfer_config.idle_cycles, 0, true) {
+            TransferResult::Ok(value) => {
+                if value & transfer_config.match_mask == expected & transfer_config.match_mask {
+                    return TransferResult::Ok(value);
+                }
+            }
+            other => return other,
+        }
+    }
+    TransferResult::Mismatch
 }
 
 fn transfer<DEPS>(
@@ -330,7 +626,7 @@ fn transfer<DEPS>(
 /// If `exit_shift` is true, it will exit the shift state (into Exit1-DR or Exit1-IR).
 ///
 /// The function will return the captured TDO data.
-fn shift_register_data<DEPS>(
+pub(crate) fn shift_register_data<DEPS>(
     jtag: &mut impl Jtag<DEPS>,
     mut data: u32,
     clock_cycles: u8,
@@ -359,7 +655,7 @@ fn shift_register_data<DEPS>(
 }
 
 /// Shift out `clocks` bits of TDI data, with TMS set to the given value.
-fn shift_repeated_tdi<DEPS>(jtag: &mut impl Jtag<DEPS>, tdi: u8, mut clocks: u16, tms: bool) {
+pub(crate) fn shift_repeated_tdi<DEPS>(jtag: &mut impl Jtag<DEPS>, tdi: u8, mut clocks: u16, tms: bool) {
     while clocks > 0 {
         let n = clocks.min(8);
         clocks -= n;
@@ -371,7 +667,7 @@ fn shift_repeated_tdi<DEPS>(jtag: &mut impl Jtag<DEPS>, tdi: u8, mut clocks: u16
 /// Shift out `clocks` (at most 8) bits of TDI data, with TMS set to the given value.
 ///
 /// Returns the captured TDO data.
-fn shift_tdi<DEPS>(jtag: &mut impl Jtag<DEPS>, tdi: u8, clocks: u8, tms: bool) -> u8 {
+pub(crate) fn shift_tdi<DEPS>(jtag: &mut impl Jtag<DEPS>, tdi: u8, clocks: u8, tms: bool) -> u8 {
     let mut tdo = 0;
     jtag.sequence(
         SequenceInfo {
@@ -390,7 +686,7 @@ fn shift_tdi<DEPS>(jtag: &mut impl Jtag<DEPS>, tdi: u8, clocks: u8, tms: bool) -
 /// Bypass bits are used to skip over TAPs in the JTAG chain. The TDI value is 1, while TMS is
 /// driven to stay in Shift-DR or Shift-IR until the last bit, where TMS is driven to 1 to exit
 /// the shift state.
-fn bypass_after_data<DEPS>(jtag: &mut impl Jtag<DEPS>, bypass_after: u16) {
+pub(crate) fn bypass_after_data<DEPS>(jtag: &mut impl Jtag<DEPS>, bypass_after: u16) {
     if bypass_after > 0 {
         if bypass_after > 1 {
             // Send the bypass bits after the DR.
@@ -0,0 +1,146 @@
+//! OpenOCD `remote_bitbang` transport.
+//!
+//! Translates OpenOCD's single-character `remote_bitbang` ASCII protocol into calls on a
+//! [`Jtag`] implementation's low-level `sequence`/`tms_sequence` primitives, so a dap-rs device
+//! can act as a generic bit-banged JTAG adapter for tools that only speak `remote_bitbang` (the
+//! microwatt verilator/urjtag flow is exactly this). The protocol itself is just a byte stream,
+//! so [`RemoteBitbang::poll`] is runtime-agnostic: this module opens no sockets and is `no_std`.
+
+use core::marker::PhantomData;
+
+use crate::jtag::{Jtag, SequenceInfo};
+
+/// Sink for the ASCII bytes produced in response to `R` commands.
+pub trait Writer {
+    fn write(&mut self, byte: u8);
+}
+
+/// Drives a [`Jtag`] implementation from `remote_bitbang` command bytes.
+///
+/// A real client writes each logical JTAG bit as a TCK=0 setup byte followed by a TCK=1 byte
+/// with the same TMS/TDI (the pulse), so only TCK's 0->1 transitions are clock edges; the
+/// in-between pin-level writes must not themselves advance the TAP. Runs of edges that leave
+/// TMS/TDI unchanged are batched into a single `SequenceInfo` call (up to 64 bits, CMSIS-DAP's
+/// own sequence limit) instead of clocking one bit at a time, which is where most of the
+/// per-bit overhead of this protocol would otherwise go.
+pub struct RemoteBitbang<'a, DEPS, J: Jtag<DEPS>> {
+    jtag: &'a mut J,
+    tck: bool,
+    tms: bool,
+    tdi: bool,
+    run_length: u8,
+    last_tdo: bool,
+    _deps: PhantomData<DEPS>,
+}
+
+impl<'a, DEPS, J: Jtag<DEPS>> RemoteBitbang<'a, DEPS, J> {
+    pub fn new(jtag: &'a mut J) -> Self {
+        Self {
+            jtag,
+            tck: false,
+            tms: false,
+            tdi: false,
+            run_length: 0,
+            last_tdo: false,
+            _deps: PhantomData,
+        }
+    }
+
+    /// Processes a chunk of `remote_bitbang` command bytes, writing the response to any `R`
+    /// command to `output`. Returns `false` once a `Q` (quit) command has been processed.
+    pub fn poll(&mut self, input: &[u8], output: &mut impl Writer) -> bool {
+        for &byte in input {
+            match byte {
+                b'0'..=b'7' => self.set_pins(byte - b'0'),
+                b'R' => {
+                    // `R` samples the TDO level already established by the last clock edge; it
+                    // does not itself advance the TAP.
+                    self.flush_run();
+                    output.write(if self.last_tdo { b'1' } else { b'0' });
+                }
+                // TRST/SRST reset lines.
+                b'r' => self.srst(true),
+                b's' => self.srst(false),
+                b't' => self.trst(true),
+                b'u' => self.trst(false),
+                // Optional LED/blink indicator.
+                b'b' => self.blink(true),
+                b'B' => self.blink(false),
+                b'Q' => {
+                    self.flush_run();
+                    return false;
+                }
+                // OpenOCD never sends anything else; ignore unknown bytes rather than panic.
+                _ => {}
+            }
+        }
+        self.flush_run();
+        true
+    }
+
+    /// Updates (TCK, TMS, TDI) from a `0`-`7` command, clocking once only on a TCK 0->1 edge.
+    fn set_pins(&mut self, bits: u8) {
+        let tck = bits & 0b100 != 0;
+        let tms = bits & 0b010 != 0;
+        let tdi = bits & 0b001 != 0;
+
+        let rising_edge = !self.tck && tck;
+        self.tck = tck;
+
+        if !rising_edge {
+            // Just a pin-level change (TCK=0 setup, or a redundant write); nothing to clock yet.
+            // `tms`/`tdi` keep the levels of the pending run until the next rising edge.
+            return;
+        }
+
+        if self.run_length > 0 && (tms != self.tms || tdi != self.tdi) {
+            self.flush_run();
+        }
+
+        self.tms = tms;
+        self.tdi = tdi;
+        self.run_length += 1;
+        if self.run_length == 64 {
+            self.flush_run();
+        }
+    }
+
+    /// Emits the batched run of identical TMS/TDI clocks as a single `SequenceInfo` call, and
+    /// records the TDO sampled by the run's last clock for a subsequent `R` to read back.
+    fn flush_run(&mut self) {
+        if self.run_length == 0 {
+            return;
+        }
+
+        let tdi_bytes = [if self.tdi { 0xFF } else { 0x00 }; 8];
+        let n_bytes = (self.run_length as usize).div_ceil(8);
+        let mut rxbuf = [0u8; 8];
+        self.jtag.sequence(
+            SequenceInfo {
+                n_bits: self.run_length,
+                capture: true,
+                tms: self.tms,
+            },
+            &tdi_bytes[..n_bytes],
+            &mut rxbuf[..n_bytes],
+        );
+
+        let last_bit = self.run_length as usize - 1;
+        self.last_tdo = rxbuf[last_bit / 8] & (1 << (last_bit % 8)) != 0;
+
+        self.run_length = 0;
+    }
+
+    fn srst(&mut self, _asserted: bool) {
+        // TRST/SRST aren't part of the `Jtag` trait; boards that wire them up do so through
+        // board-specific GPIO handling outside this crate.
+    }
+
+    fn trst(&mut self, _asserted: bool) {
+        // See `srst` above.
+    }
+
+    fn blink(&mut self, _on: bool) {
+        // No LED is wired up by default.
+    }
+}